@@ -0,0 +1,235 @@
+//
+// Copyright (c) 2017, 2022 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+
+/// The outcome of a key expression canonization check.
+///
+///     - **Z_KEYEXPR_CANON_SUCCESS**: the key expression was already in canon form.
+///     - **Z_KEYEXPR_CANON_LONE_DOLLAR_STAR**: a chunk was the lone DSL token ``$*``.
+///     - **Z_KEYEXPR_CANON_SINGLE_STAR_AFTER_DOUBLE_STAR**: a ``*`` chunk followed a ``**`` chunk.
+///     - **Z_KEYEXPR_CANON_DOUBLE_STAR_AFTER_DOUBLE_STAR**: a ``**`` chunk followed a ``**`` chunk.
+///     - **Z_KEYEXPR_CANON_EMPTY_CHUNK**: the key expression contained an empty chunk.
+///     - **Z_KEYEXPR_CANON_STARS_IN_CHUNK**: a ``*`` appeared where it was not the whole chunk.
+///     - **Z_KEYEXPR_CANON_DOLLAR_AFTER_DOLLAR_OR_STAR**: a ``$`` followed a ``$`` or a ``*``.
+///     - **Z_KEYEXPR_CANON_CONTAINS_SHARP_OR_QMARK**: the key expression contained a ``#`` or ``?``.
+///     - **Z_KEYEXPR_CANON_CONTAINS_UNBOUND_DOLLAR**: a ``$`` was not part of a ``$*`` token.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum zp_keyexpr_canon_status_t {
+    /// The key expression is canon.
+    Z_KEYEXPR_CANON_SUCCESS = 0,
+    /// A chunk was the lone DSL token `$*`, which must be written as `*`.
+    Z_KEYEXPR_CANON_LONE_DOLLAR_STAR = -1,
+    /// A `*` chunk followed a `**` chunk: `**/*` must be written as `*/**`.
+    Z_KEYEXPR_CANON_SINGLE_STAR_AFTER_DOUBLE_STAR = -2,
+    /// A `**` chunk followed a `**` chunk: `**/**` must be collapsed to `**`.
+    Z_KEYEXPR_CANON_DOUBLE_STAR_AFTER_DOUBLE_STAR = -3,
+    /// The key expression contained an empty chunk (a leading, trailing or doubled `/`).
+    Z_KEYEXPR_CANON_EMPTY_CHUNK = -4,
+    /// A `*` appeared inside a chunk without being the whole chunk.
+    Z_KEYEXPR_CANON_STARS_IN_CHUNK = -5,
+    /// A `$` immediately followed a `$` or a `*`.
+    Z_KEYEXPR_CANON_DOLLAR_AFTER_DOLLAR_OR_STAR = -6,
+    /// The key expression contained a `#` or a `?`.
+    Z_KEYEXPR_CANON_CONTAINS_SHARP_OR_QMARK = -7,
+    /// A `$` was not the start of a `$*` token.
+    Z_KEYEXPR_CANON_CONTAINS_UNBOUND_DOLLAR = -8,
+}
+
+use zp_keyexpr_canon_status_t::*;
+
+/// The shape a chunk takes once it has passed validation.
+enum ChunkKind {
+    /// A single-chunk wildcard `*` (or the normalized `$*`).
+    Single,
+    /// A multi-chunk wildcard `**`.
+    Double,
+    /// Any other (valid) literal chunk.
+    Verbatim,
+}
+
+/// Validates a single `/`-free chunk, returning its kind and whether it had to be
+/// normalized from the lone `$*` DSL token into `*`.
+fn validate_chunk(chunk: &[u8]) -> Result<(ChunkKind, bool), zp_keyexpr_canon_status_t> {
+    if chunk.is_empty() {
+        return Err(Z_KEYEXPR_CANON_EMPTY_CHUNK);
+    }
+    match chunk {
+        b"*" => return Ok((ChunkKind::Single, false)),
+        b"**" => return Ok((ChunkKind::Double, false)),
+        b"$*" => return Ok((ChunkKind::Single, true)),
+        _ => {}
+    }
+    let mut i = 0;
+    while i < chunk.len() {
+        match chunk[i] {
+            b'#' | b'?' => return Err(Z_KEYEXPR_CANON_CONTAINS_SHARP_OR_QMARK),
+            b'$' => {
+                if chunk.get(i + 1) == Some(&b'$') {
+                    return Err(Z_KEYEXPR_CANON_DOLLAR_AFTER_DOLLAR_OR_STAR);
+                }
+                if chunk.get(i + 1) != Some(&b'*') {
+                    return Err(Z_KEYEXPR_CANON_CONTAINS_UNBOUND_DOLLAR);
+                }
+                if chunk.get(i + 2) == Some(&b'$') {
+                    return Err(Z_KEYEXPR_CANON_DOLLAR_AFTER_DOLLAR_OR_STAR);
+                }
+                i += 2;
+            }
+            b'*' => return Err(Z_KEYEXPR_CANON_STARS_IN_CHUNK),
+            _ => i += 1,
+        }
+    }
+    Ok((ChunkKind::Verbatim, false))
+}
+
+/// A chunk scheduled to be written back during canonization.
+enum CanonChunk {
+    Single,
+    Double,
+    Verbatim(usize, usize),
+}
+
+/// Canonizes `buf` in place, returning the new length and the status of the operation.
+///
+/// The canon form is never longer than the input, so the write cursor always trails
+/// the read cursor and the rewrite is safe to perform on a single buffer.
+fn canonize(buf: &mut [u8]) -> (usize, zp_keyexpr_canon_status_t) {
+    let mut out: Vec<CanonChunk> = Vec::new();
+    let mut last_double = false;
+    let mut start = 0;
+    loop {
+        let end = match buf[start..].iter().position(|&b| b == b'/') {
+            Some(p) => start + p,
+            None => buf.len(),
+        };
+        match validate_chunk(&buf[start..end]) {
+            Ok((kind, _)) => match kind {
+                ChunkKind::Double => {
+                    if !last_double {
+                        out.push(CanonChunk::Double);
+                        last_double = true;
+                    }
+                }
+                ChunkKind::Single => {
+                    if last_double {
+                        out.insert(out.len() - 1, CanonChunk::Single);
+                    } else {
+                        out.push(CanonChunk::Single);
+                    }
+                }
+                ChunkKind::Verbatim => {
+                    out.push(CanonChunk::Verbatim(start, end));
+                    last_double = false;
+                }
+            },
+            Err(status) => return (buf.len(), status),
+        }
+        if end == buf.len() {
+            break;
+        }
+        start = end + 1;
+    }
+
+    let mut w = 0;
+    for (i, chunk) in out.iter().enumerate() {
+        if i > 0 {
+            buf[w] = b'/';
+            w += 1;
+        }
+        match *chunk {
+            CanonChunk::Single => {
+                buf[w] = b'*';
+                w += 1;
+            }
+            CanonChunk::Double => {
+                buf[w] = b'*';
+                buf[w + 1] = b'*';
+                w += 2;
+            }
+            CanonChunk::Verbatim(s, e) => {
+                buf.copy_within(s..e, w);
+                w += e - s;
+            }
+        }
+    }
+    (w, Z_KEYEXPR_CANON_SUCCESS)
+}
+
+/// Returns ``Z_KEYEXPR_CANON_SUCCESS`` if the passed string is in canon form, or the
+/// first canon rule it violates otherwise.
+///
+/// Parameters:
+///     start: A pointer to the start of the key expression.
+///     len: The length in bytes of the key expression.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_keyexpr_is_canon(
+    start: *const libc::c_char,
+    len: usize,
+) -> zp_keyexpr_canon_status_t {
+    let name = std::slice::from_raw_parts(start as *const u8, len);
+    let mut last_double = false;
+    let mut offset = 0;
+    loop {
+        let end = match name[offset..].iter().position(|&b| b == b'/') {
+            Some(p) => offset + p,
+            None => name.len(),
+        };
+        match validate_chunk(&name[offset..end]) {
+            Ok((_, true)) => return Z_KEYEXPR_CANON_LONE_DOLLAR_STAR,
+            Ok((ChunkKind::Double, false)) => {
+                if last_double {
+                    return Z_KEYEXPR_CANON_DOUBLE_STAR_AFTER_DOUBLE_STAR;
+                }
+                last_double = true;
+            }
+            Ok((ChunkKind::Single, false)) => {
+                if last_double {
+                    return Z_KEYEXPR_CANON_SINGLE_STAR_AFTER_DOUBLE_STAR;
+                }
+                last_double = false;
+            }
+            Ok((ChunkKind::Verbatim, false)) => last_double = false,
+            Err(status) => return status,
+        }
+        if end == name.len() {
+            break;
+        }
+        offset = end + 1;
+    }
+    Z_KEYEXPR_CANON_SUCCESS
+}
+
+/// Canonizes the passed string in place, possibly shortening it by modifying ``len``.
+///
+/// Returns ``Z_KEYEXPR_CANON_SUCCESS`` if the operation was a success, or the reason
+/// the key expression could not be canonized otherwise.
+///
+/// Parameters:
+///     start: A pointer to the start of the key expression.
+///     len: A pointer to the length in bytes of the key expression, updated in place.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_keyexpr_canonize(
+    start: *mut libc::c_char,
+    len: *mut usize,
+) -> zp_keyexpr_canon_status_t {
+    let name = std::slice::from_raw_parts_mut(start as *mut u8, *len);
+    let (new_len, status) = canonize(name);
+    if status == Z_KEYEXPR_CANON_SUCCESS {
+        *len = new_len;
+    }
+    status
+}
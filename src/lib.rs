@@ -37,6 +37,10 @@ mod pull_subscriber;
 pub use crate::pull_subscriber::*;
 mod publisher;
 pub use crate::publisher::*;
+#[cfg(feature = "shared-memory")]
+mod shm;
+#[cfg(feature = "shared-memory")]
+pub use crate::shm::*;
 mod closures;
 pub use closures::*;
 
@@ -93,6 +97,136 @@ pub extern "C" fn zc_init_logger() {
     let _ = env_logger::try_init();
 }
 
+/// The severity level of a log record forwarded to a :c:type:`z_owned_closure_log_t`.
+///
+///     - **Z_LOG_SEVERITY_TRACE**
+///     - **Z_LOG_SEVERITY_DEBUG**
+///     - **Z_LOG_SEVERITY_INFO**
+///     - **Z_LOG_SEVERITY_WARN**
+///     - **Z_LOG_SEVERITY_ERROR**
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum zc_log_severity_t {
+    TRACE,
+    DEBUG,
+    INFO,
+    WARN,
+    ERROR,
+}
+
+impl From<log::Level> for zc_log_severity_t {
+    #[inline]
+    fn from(l: log::Level) -> Self {
+        match l {
+            log::Level::Trace => zc_log_severity_t::TRACE,
+            log::Level::Debug => zc_log_severity_t::DEBUG,
+            log::Level::Info => zc_log_severity_t::INFO,
+            log::Level::Warn => zc_log_severity_t::WARN,
+            log::Level::Error => zc_log_severity_t::ERROR,
+        }
+    }
+}
+
+impl From<zc_log_severity_t> for log::LevelFilter {
+    #[inline]
+    fn from(s: zc_log_severity_t) -> Self {
+        match s {
+            zc_log_severity_t::TRACE => log::LevelFilter::Trace,
+            zc_log_severity_t::DEBUG => log::LevelFilter::Debug,
+            zc_log_severity_t::INFO => log::LevelFilter::Info,
+            zc_log_severity_t::WARN => log::LevelFilter::Warn,
+            zc_log_severity_t::ERROR => log::LevelFilter::Error,
+        }
+    }
+}
+
+/// A closure is a structure that contains all the elements for stateful, memory-leak-free callbacks:
+///
+/// Members:
+///   void *context: a pointer to an arbitrary state.
+///   void *call(zc_log_severity_t severity, const char *message, const void *context): the typical callback function. `context` will be passed as its last argument.
+///   void *drop(void*): allows the callback's state to be freed.
+///
+/// Closures are not guaranteed not to be called concurrently.
+///
+/// It is guaranteed that:
+///   - `call` will never be called once `drop` has started.
+///   - `drop` will only be called **once**, and **after every** `call` has ended.
+///   - The two previous guarantees imply that `call` and `drop` are never called concurrently.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct z_owned_closure_log_t {
+    context: *mut libc::c_void,
+    call: Option<extern "C" fn(zc_log_severity_t, *const libc::c_char, *mut libc::c_void)>,
+    drop: Option<extern "C" fn(*mut libc::c_void)>,
+}
+
+/// A logger that formats each record and forwards it to a C closure.
+struct ClosureLogger(z_owned_closure_log_t);
+
+// The wrapped closure may be invoked concurrently from any thread that logs;
+// the C side is responsible for the thread-safety of its `context`.
+unsafe impl Send for ClosureLogger {}
+unsafe impl Sync for ClosureLogger {}
+
+impl log::Log for ClosureLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        self.0.call.is_some()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Some(call) = self.0.call {
+            if let Ok(message) = std::ffi::CString::new(format!("{}", record.args())) {
+                call(record.level().into(), message.as_ptr(), self.0.context);
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl Drop for ClosureLogger {
+    fn drop(&mut self) {
+        if let Some(drop) = self.0.drop {
+            drop(self.0.context);
+        }
+    }
+}
+
+/// Initialises the zenoh runtime logger, routing each record to `callback` instead of stderr.
+///
+/// The closure receives the record's :c:type:`zc_log_severity_t` and a null-terminated
+/// message string. It lets applications capture zenoh diagnostics into their own logging
+/// infrastructure rather than env_logger's fixed format. Records less severe than
+/// `max_level` are filtered out before reaching the closure.
+///
+/// Returns ``0`` in case of success, a negative value if a logger was already installed
+/// (in which case the closure is dropped). In both cases `callback` is consumed.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub extern "C" fn zc_init_logger_with_callback(
+    callback: &mut z_owned_closure_log_t,
+    max_level: zc_log_severity_t,
+) -> i8 {
+    let mut closure = z_owned_closure_log_t {
+        context: std::ptr::null_mut(),
+        call: None,
+        drop: None,
+    };
+    std::mem::swap(callback, &mut closure);
+    match log::set_boxed_logger(Box::new(ClosureLogger(closure))) {
+        Ok(()) => {
+            log::set_max_level(max_level.into());
+            0
+        }
+        Err(e) => {
+            log::debug!("{}", e);
+            i8::MIN
+        }
+    }
+}
+
 fn copy_to_libc(s: &[u8]) -> *mut libc::c_char {
     unsafe {
         let string = libc::malloc(s.len() + 1) as *mut libc::c_char;
@@ -0,0 +1,128 @@
+//
+// Copyright (c) 2017, 2022 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+use crate::z_closure_hello_call;
+use crate::z_id_t;
+use crate::z_owned_closure_hello_t;
+use crate::z_owned_scouting_config_t;
+use crate::z_str_array_t;
+use zenoh::config::WhatAmI;
+use zenoh::config::WhatAmIMatcher;
+use zenoh::prelude::sync::SyncResolve;
+
+/// A bitmask of the node roles to discover while scouting, or of the role a
+/// discovered node advertises.
+///
+/// The flags :c:macro:`Z_WHATAMI_ROUTER`, :c:macro:`Z_WHATAMI_PEER` and
+/// :c:macro:`Z_WHATAMI_CLIENT` may be OR-ed together to scout for several roles
+/// at once.
+#[allow(non_camel_case_types)]
+pub type z_whatami_t = u8;
+
+/// The router role.
+pub const Z_WHATAMI_ROUTER: z_whatami_t = 1;
+/// The peer role.
+pub const Z_WHATAMI_PEER: z_whatami_t = 1 << 1;
+/// The client role.
+pub const Z_WHATAMI_CLIENT: z_whatami_t = 1 << 2;
+
+/// Converts a single-role :c:type:`WhatAmI` into its `z_whatami_t` flag.
+fn whatami_to_flag(what: WhatAmI) -> z_whatami_t {
+    match what {
+        WhatAmI::Router => Z_WHATAMI_ROUTER,
+        WhatAmI::Peer => Z_WHATAMI_PEER,
+        WhatAmI::Client => Z_WHATAMI_CLIENT,
+    }
+}
+
+/// Converts a `z_whatami_t` bitmask into a :c:type:`WhatAmIMatcher`, falling back to
+/// matching every role if the mask is empty or unknown.
+fn whatami_to_matcher(what: z_whatami_t) -> WhatAmIMatcher {
+    let all = WhatAmI::Router | WhatAmI::Peer | WhatAmI::Client;
+    if what == 0 {
+        return all;
+    }
+    WhatAmIMatcher::try_from(what).unwrap_or(all)
+}
+
+/// Scout for zenoh entities whose role matches the `what` bitmask.
+///
+/// Parameters:
+///     what: A bitmask of :c:type:`z_whatami_t` flags OR-ed together, restricting
+///           which node roles are discovered (e.g. `Z_WHATAMI_ROUTER | Z_WHATAMI_PEER`).
+///     config: The scouting configuration. This function takes ownership of it.
+///     callback: The callback invoked for each discovered entity.
+///
+/// Returns ``0`` in case of success, a negative value in case of failure.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub extern "C" fn z_scout(
+    what: z_whatami_t,
+    config: &mut z_owned_scouting_config_t,
+    callback: &mut z_owned_closure_hello_t,
+) -> i8 {
+    let mut closure = z_owned_closure_hello_t::empty();
+    std::mem::swap(callback, &mut closure);
+    let config = match config.as_mut().take() {
+        Some(config) => config,
+        None => {
+            log::error!("Invalid scouting config");
+            return i8::MIN;
+        }
+    };
+    match zenoh::scout(whatami_to_matcher(what), *config)
+        .callback(move |hello| z_closure_hello_call(&closure, &hello.into()))
+        .res_sync()
+    {
+        Ok(_) => 0,
+        Err(e) => {
+            log::error!("{}", e);
+            i8::MIN
+        }
+    }
+}
+
+/// Returns the role advertised by a discovered entity as a :c:type:`z_whatami_t` flag.
+#[no_mangle]
+pub extern "C" fn z_hello_whatami(hello: &z_hello_t) -> z_whatami_t {
+    hello.whatami
+}
+
+/// A zenoh-allocated hello message returned by a scout, describing a discovered entity.
+///
+/// Members:
+///     z_whatami_t whatami: The role the discovered entity advertises.
+///     z_id_t zid: The Zenoh ID of the discovered entity.
+///     z_str_array_t locators: The locators at which the discovered entity can be reached.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct z_hello_t {
+    pub whatami: z_whatami_t,
+    pub zid: z_id_t,
+    pub locators: z_str_array_t,
+}
+
+impl From<zenoh::scouting::Hello> for z_hello_t {
+    fn from(hello: zenoh::scouting::Hello) -> Self {
+        let locators = hello
+            .locators
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>();
+        z_hello_t {
+            whatami: whatami_to_flag(hello.whatami),
+            zid: hello.zid.into(),
+            locators: locators.into(),
+        }
+    }
+}
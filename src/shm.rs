@@ -0,0 +1,310 @@
+//
+// Copyright (c) 2017, 2022 ZettaScale Technology.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh team, <zenoh@zettascale.tech>
+//
+use crate::commons::*;
+use crate::keyexpr::*;
+use crate::platform::z_owned_session_t;
+use crate::session::*;
+use crate::z_publisher_t;
+use crate::LOG_INVALID_SESSION;
+use std::ffi::CStr;
+use zenoh::prelude::sync::SyncResolve;
+use zenoh::shm::{SharedMemoryBuf, SharedMemoryManager};
+
+/// An owned shared-memory manager, backing the allocation of zero-copy payloads.
+///
+/// A manager owns a named POSIX shared-memory segment of a fixed size; buffers
+/// allocated from it can be published without copying through the network stack
+/// when both ends of the link live on the same host.
+///
+/// Like all `z_owned_X_t`, an instance will be destroyed by any function which takes a mutable pointer to said instance, as this implies the instance's inners were moved.
+/// To make this fact more obvious when reading your code, consider using `z_move(val)` instead of `&val` as the argument.
+/// After a move, `val` will still exist, but will no longer be valid. The destructors are double-drop-safe, but other functions will still trust that your `val` is valid.
+///
+/// To check if `val` is still valid, you may use `z_X_check(&val)` or `z_check(val)` if your compiler supports `_Generic`, which will return `true` if `val` is valid.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct z_owned_shm_manager_t([usize; 1]);
+
+type Manager = Option<Box<SharedMemoryManager>>;
+
+impl From<Manager> for z_owned_shm_manager_t {
+    fn from(m: Manager) -> Self {
+        unsafe { std::mem::transmute(m) }
+    }
+}
+
+impl AsRef<Manager> for z_owned_shm_manager_t {
+    fn as_ref(&self) -> &Manager {
+        unsafe { std::mem::transmute(self) }
+    }
+}
+
+impl AsMut<Manager> for z_owned_shm_manager_t {
+    fn as_mut(&mut self) -> &mut Manager {
+        unsafe { std::mem::transmute(self) }
+    }
+}
+
+impl z_owned_shm_manager_t {
+    pub fn null() -> Self {
+        None.into()
+    }
+}
+
+/// Creates a new shared-memory manager with the given segment `id` and `size` in bytes.
+///
+/// The manager must be created against a valid session whose shared-memory transport
+/// is enabled (`transport/shared_memory/enabled` in the session's configuration); buffers
+/// allocated from it can then be published on that session and mapped by peers on the
+/// same host.
+///
+/// Parameters:
+///     session: A valid zenoh session with shared-memory transport enabled.
+///     id: A null-terminated string naming the shared-memory segment.
+///     size: The size in bytes of the shared-memory segment.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_shm_manager_new(
+    session: z_session_t,
+    id: *const libc::c_char,
+    size: usize,
+) -> z_owned_shm_manager_t {
+    let session: &'static z_owned_session_t = session.into();
+    if session.as_ref().is_none() {
+        log::debug!("{}", LOG_INVALID_SESSION);
+        return z_owned_shm_manager_t::null();
+    }
+    let id = match CStr::from_ptr(id).to_str() {
+        Ok(id) => id.to_string(),
+        Err(e) => {
+            log::debug!("{}", e);
+            return z_owned_shm_manager_t::null();
+        }
+    };
+    match SharedMemoryManager::make(id, size) {
+        Ok(manager) => Some(Box::new(manager)).into(),
+        Err(e) => {
+            log::debug!("{}", e);
+            z_owned_shm_manager_t::null()
+        }
+    }
+}
+
+/// Constructs a null safe-to-drop value of 'z_owned_shm_manager_t' type
+#[no_mangle]
+pub extern "C" fn z_shm_manager_null() -> z_owned_shm_manager_t {
+    z_owned_shm_manager_t::null()
+}
+
+/// Returns ``true`` if `manager` is valid.
+#[no_mangle]
+pub extern "C" fn z_shm_manager_check(manager: &z_owned_shm_manager_t) -> bool {
+    manager.as_ref().is_some()
+}
+
+/// Drops the given shared-memory manager, invalidating it for double-drop safety.
+#[no_mangle]
+pub extern "C" fn z_shm_manager_drop(manager: &mut z_owned_shm_manager_t) {
+    let _ = manager.as_mut().take();
+}
+
+/// Performs a garbage collection pass over the manager's segment, returning the
+/// number of bytes that were reclaimed from buffers no longer in use.
+#[no_mangle]
+pub extern "C" fn z_shm_manager_gc(manager: &mut z_owned_shm_manager_t) -> usize {
+    match manager.as_mut() {
+        Some(m) => m.garbage_collect(),
+        None => 0,
+    }
+}
+
+/// An owned shared-memory buffer allocated from a :c:type:`z_owned_shm_manager_t`.
+///
+/// Like all `z_owned_X_t`, an instance will be destroyed by any function which takes a mutable pointer to said instance, as this implies the instance's inners were moved.
+/// To make this fact more obvious when reading your code, consider using `z_move(val)` instead of `&val` as the argument.
+/// After a move, `val` will still exist, but will no longer be valid. The destructors are double-drop-safe, but other functions will still trust that your `val` is valid.
+///
+/// To check if `val` is still valid, you may use `z_X_check(&val)` or `z_check(val)` if your compiler supports `_Generic`, which will return `true` if `val` is valid.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct z_owned_shm_buf_t([usize; 1]);
+
+type ShmBuf = Option<Box<SharedMemoryBuf>>;
+
+impl From<ShmBuf> for z_owned_shm_buf_t {
+    fn from(b: ShmBuf) -> Self {
+        unsafe { std::mem::transmute(b) }
+    }
+}
+
+impl AsRef<ShmBuf> for z_owned_shm_buf_t {
+    fn as_ref(&self) -> &ShmBuf {
+        unsafe { std::mem::transmute(self) }
+    }
+}
+
+impl AsMut<ShmBuf> for z_owned_shm_buf_t {
+    fn as_mut(&mut self) -> &mut ShmBuf {
+        unsafe { std::mem::transmute(self) }
+    }
+}
+
+impl z_owned_shm_buf_t {
+    pub fn null() -> Self {
+        None.into()
+    }
+}
+
+/// A loaned shared-memory buffer.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct z_shm_buf_t(*const z_owned_shm_buf_t);
+
+impl From<&z_owned_shm_buf_t> for z_shm_buf_t {
+    fn from(b: &z_owned_shm_buf_t) -> Self {
+        z_shm_buf_t(b as *const _)
+    }
+}
+
+impl From<z_shm_buf_t> for &'static z_owned_shm_buf_t {
+    fn from(b: z_shm_buf_t) -> Self {
+        unsafe { &*b.0 }
+    }
+}
+
+/// Allocates a buffer of `capacity` bytes from the given manager.
+///
+/// Returns a null buffer if the segment is exhausted; call :c:func:`z_shm_manager_gc`
+/// to reclaim buffers that are no longer referenced and retry.
+#[no_mangle]
+pub extern "C" fn z_shm_alloc(
+    manager: &mut z_owned_shm_manager_t,
+    capacity: usize,
+) -> z_owned_shm_buf_t {
+    match manager.as_mut() {
+        Some(m) => match m.alloc(capacity) {
+            Ok(buf) => Some(Box::new(buf)).into(),
+            Err(e) => {
+                log::debug!("{}", e);
+                z_owned_shm_buf_t::null()
+            }
+        },
+        None => z_owned_shm_buf_t::null(),
+    }
+}
+
+/// Constructs a null safe-to-drop value of 'z_owned_shm_buf_t' type
+#[no_mangle]
+pub extern "C" fn z_shm_buf_null() -> z_owned_shm_buf_t {
+    z_owned_shm_buf_t::null()
+}
+
+/// Returns ``true`` if `buf` is valid.
+#[no_mangle]
+pub extern "C" fn z_shm_buf_check(buf: &z_owned_shm_buf_t) -> bool {
+    buf.as_ref().is_some()
+}
+
+/// Drops the given shared-memory buffer, invalidating it for double-drop safety.
+#[no_mangle]
+pub extern "C" fn z_shm_buf_drop(buf: &mut z_owned_shm_buf_t) {
+    let _ = buf.as_mut().take();
+}
+
+/// Returns a :c:type:`z_shm_buf_t` loaned from `buf`.
+#[no_mangle]
+pub extern "C" fn z_shm_buf_loan(buf: &z_owned_shm_buf_t) -> z_shm_buf_t {
+    buf.into()
+}
+
+/// Returns a mutable pointer to the start of the buffer's contents, or ``NULL`` if the buffer is invalid.
+///
+/// The buffer is taken mutably so the returned pointer may be written through to fill
+/// the buffer before it is published.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_shm_buf_ptr(buf: &mut z_owned_shm_buf_t) -> *mut u8 {
+    match buf.as_mut() {
+        Some(b) => b.as_mut_slice().as_mut_ptr(),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the length in bytes of the buffer's contents.
+#[no_mangle]
+pub extern "C" fn z_shm_buf_length(buf: z_shm_buf_t) -> usize {
+    let buf: &z_owned_shm_buf_t = buf.into();
+    match buf.as_ref() {
+        Some(b) => b.len(),
+        None => 0,
+    }
+}
+
+/// Puts the contents of a shared-memory buffer for a given key expression.
+///
+/// This is the zero-copy counterpart of :c:func:`z_put`: instead of copying a
+/// `z_bytes_t` through the network stack, ownership of `buf` is moved into the
+/// session, which transmits a handle to the backing segment. `buf` is consumed.
+///
+/// Returns ``0`` in case of success, a negative value in case of failure.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_put_shm(
+    session: z_session_t,
+    keyexpr: z_keyexpr_t,
+    buf: &mut z_owned_shm_buf_t,
+) -> i8 {
+    let session: &'static z_owned_session_t = session.into();
+    let buf = match buf.as_mut().take() {
+        Some(buf) => *buf,
+        None => return i8::MIN,
+    };
+    match session.as_ref() {
+        Some(s) => match s.put(keyexpr, buf).res_sync() {
+            Ok(()) => 0,
+            Err(e) => {
+                log::error!("{}", e);
+                i8::MIN
+            }
+        },
+        None => {
+            log::debug!("{}", LOG_INVALID_SESSION);
+            i8::MIN
+        }
+    }
+}
+
+/// Sends the contents of a shared-memory buffer through a publisher, without copying.
+///
+/// This is the zero-copy counterpart of :c:func:`z_publisher_put`. `buf` is consumed.
+///
+/// Returns ``0`` in case of success, a negative value in case of failure.
+#[no_mangle]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe extern "C" fn z_publisher_put_shm(
+    publisher: z_publisher_t,
+    buf: &mut z_owned_shm_buf_t,
+) -> i8 {
+    let buf = match buf.as_mut().take() {
+        Some(buf) => *buf,
+        None => return i8::MIN,
+    };
+    match publisher.as_ref().put(buf).res_sync() {
+        Ok(()) => 0,
+        Err(e) => {
+            log::error!("{}", e);
+            i8::MIN
+        }
+    }
+}
@@ -206,7 +206,14 @@ pub unsafe extern "C" fn z_declare_subscriber(
             let res = s
                 .declare_subscriber(keyexpr)
                 .callback(move |sample| {
-                    let payload = sample.payload.contiguous();
+                    // Borrow the payload in place when it is a single contiguous region,
+                    // avoiding an allocation; only fragmented buffers fall back to the
+                    // copying `contiguous()`.
+                    let mut slices = sample.payload.slices();
+                    let payload: std::borrow::Cow<[u8]> = match (slices.next(), slices.next()) {
+                        (Some(slice), None) => std::borrow::Cow::Borrowed(slice),
+                        _ => std::borrow::Cow::Owned(sample.payload.contiguous().into_owned()),
+                    };
                     let bytes = z_bytes_t {
                         start: payload.as_ptr(),
                         len: payload.len(),